@@ -1,7 +1,10 @@
 use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 use sha3::{CShake256, CShake256Core, digest::{Update, ExtendableOutput, XofReader}};
+use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // Xoshiro256++ PRNG
@@ -37,6 +40,81 @@ impl Xoshiro256PlusPlus {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// SipHash-2-4
+// ═══════════════════════════════════════════════════════════════════════════════
+
+// Keyed SipHash-2-4 over a single 64-bit message word, used as the round function
+// of the Feistel permutation below.
+#[inline]
+fn siphash24(k0: u64, k1: u64, data: u64) -> u64 {
+    let mut v0 = k0 ^ 0x736f_6d65_7073_6575;
+    let mut v1 = k1 ^ 0x646f_7261_6e64_6f6d;
+    let mut v2 = k0 ^ 0x6c79_6765_6e65_7261;
+    let mut v3 = k1 ^ 0x7465_6462_7974_6573;
+
+    macro_rules! round {
+        () => {
+            v0 = v0.wrapping_add(v1);
+            v1 = v1.rotate_left(13);
+            v1 ^= v0;
+            v0 = v0.rotate_left(32);
+            v2 = v2.wrapping_add(v3);
+            v3 = v3.rotate_left(16);
+            v3 ^= v2;
+            v0 = v0.wrapping_add(v3);
+            v3 = v3.rotate_left(21);
+            v3 ^= v0;
+            v2 = v2.wrapping_add(v1);
+            v1 = v1.rotate_left(17);
+            v1 ^= v2;
+            v2 = v2.rotate_left(32);
+        };
+    }
+
+    // One full 8-byte message block, then the length-tagged finalization block.
+    v3 ^= data;
+    round!();
+    round!();
+    v0 ^= data;
+
+    let b = 8u64 << 56;
+    v3 ^= b;
+    round!();
+    round!();
+    v0 ^= b;
+
+    v2 ^= 0xff;
+    round!();
+    round!();
+    round!();
+    round!();
+
+    v0 ^ v1 ^ v2 ^ v3
+}
+
+// Fixed shared key for the nonce permutation. Every worker uses the SAME key so
+// the map is one global 64-bit bijection; workers differ only in which inputs
+// they feed it (see `WorkerState`).
+const FEISTEL_K0: u64 = 0x0706_0504_0302_0100;
+const FEISTEL_K1: u64 = 0x0f0e_0d0c_0b0a_0908;
+
+// Balanced 4-round Feistel network over a 64-bit word keyed by SipHash-2-4. A
+// Feistel cipher is a permutation for any round function, so this is an injective
+// (collision-free) map from counter to nonce: distinct inputs never collide.
+#[inline]
+fn feistel_permute(x: u64) -> u64 {
+    let mut l = (x >> 32) as u32;
+    let mut r = x as u32;
+    for round in 0..4u64 {
+        let f = siphash24(FEISTEL_K0, FEISTEL_K1 ^ round, r as u64) as u32;
+        let next = l ^ f;
+        l = r;
+        r = next;
+    }
+    ((l as u64) << 32) | r as u64
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Matrix Operations
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -113,15 +191,92 @@ fn generate_matrix_internal(pre_pow_hash: &[u8; 32]) -> Matrix {
     }
 }
 
+// Matrix-vector product for HeavyHash. Every product is at most 15×15 = 225 and
+// each row sum is at most 64×225 = 14400, so the accumulator stays within a u16.
+#[inline]
+fn matrix_mul(matrix: &Matrix, v: &[u16; 64], p: &mut [u16; 64]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: guarded by the runtime AVX2 feature check above.
+            unsafe { matrix_mul_avx2(matrix, v, p) };
+            return;
+        }
+    }
+    matrix_mul_scalar(matrix, v, p);
+}
+
+#[inline]
+fn matrix_mul_scalar(matrix: &Matrix, v: &[u16; 64], p: &mut [u16; 64]) {
+    for i in 0..64 {
+        let mut sum: u16 = 0;
+        for j in 0..64 {
+            sum += matrix[i][j] * v[j];
+        }
+        p[i] = (sum >> 10) & 0x0F;
+    }
+}
+
+// AVX2 kernel: load 16 matrix entries and 16 vector entries per `__m256i` and use
+// `_mm256_madd_epi16` (signed 16×16→32-bit with pairwise add) to accumulate one
+// row's partial sums in eight 32-bit lanes across the four 16-wide loads, then reduce.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn matrix_mul_avx2(matrix: &Matrix, v: &[u16; 64], p: &mut [u16; 64]) {
+    use std::arch::x86_64::*;
+
+    let vp = v.as_ptr() as *const __m256i;
+    let v0 = _mm256_loadu_si256(vp);
+    let v1 = _mm256_loadu_si256(vp.add(1));
+    let v2 = _mm256_loadu_si256(vp.add(2));
+    let v3 = _mm256_loadu_si256(vp.add(3));
+
+    for i in 0..64 {
+        let mp = matrix[i].as_ptr() as *const __m256i;
+        let mut acc = _mm256_madd_epi16(_mm256_loadu_si256(mp), v0);
+        acc = _mm256_add_epi32(acc, _mm256_madd_epi16(_mm256_loadu_si256(mp.add(1)), v1));
+        acc = _mm256_add_epi32(acc, _mm256_madd_epi16(_mm256_loadu_si256(mp.add(2)), v2));
+        acc = _mm256_add_epi32(acc, _mm256_madd_epi16(_mm256_loadu_si256(mp.add(3)), v3));
+
+        // Horizontally reduce the eight 32-bit lanes into the final row sum.
+        let lo = _mm256_castsi256_si128(acc);
+        let hi = _mm256_extracti128_si256(acc, 1);
+        let mut s = _mm_add_epi32(lo, hi);
+        s = _mm_add_epi32(s, _mm_shuffle_epi32(s, 0b01_00_11_10));
+        s = _mm_add_epi32(s, _mm_shuffle_epi32(s, 0b00_00_00_01));
+        let sum = _mm_cvtsi128_si32(s) as u32;
+
+        p[i] = ((sum >> 10) & 0x0F) as u16;
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // cSHAKE256
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// The two cSHAKE256 cores with their customization strings already absorbed.
+/// Built once per template so the inner loop only absorbs the variable data.
+#[derive(Clone)]
+struct ShakeCores {
+    pow: CShake256Core,
+    heavy: CShake256Core,
+}
+
+impl ShakeCores {
+    fn new() -> Self {
+        Self {
+            pow: CShake256Core::new(b"ProofOfWorkHash"),
+            heavy: CShake256Core::new(b"HeavyHash"),
+        }
+    }
+}
+
+/// Absorb `data` into a clone of a pre-initialized core and squeeze `output_len` bytes.
 #[inline]
-fn cshake256(custom: &[u8], data: &[u8], output_len: usize) -> Vec<u8> {
-    let hasher = CShake256::from_core(CShake256Core::new(custom))
+fn cshake256_core(core: &CShake256Core, data: &[u8], output_len: usize) -> Vec<u8> {
+    let hasher = CShake256::from_core(core.clone())
         .chain(data);
-    
+
     let mut output = vec![0u8; output_len];
     hasher.finalize_xof().read(&mut output);
     output
@@ -132,7 +287,7 @@ fn cshake256(custom: &[u8], data: &[u8], output_len: usize) -> Vec<u8> {
 // ═══════════════════════════════════════════════════════════════════════════════
 
 #[inline]
-fn heavy_hash_internal(matrix: &Matrix, hash: &[u8; 32]) -> [u8; 32] {
+fn heavy_hash_internal(matrix: &Matrix, hash: &[u8; 32], cores: &ShakeCores) -> [u8; 32] {
     // Expand to 64 x 4-bit values
     let mut v = [0u16; 64];
     for i in 0..32 {
@@ -141,15 +296,9 @@ fn heavy_hash_internal(matrix: &Matrix, hash: &[u8; 32]) -> [u8; 32] {
     }
     
     // Matrix multiplication
-    let mut p = [0u64; 64];
-    for i in 0..64 {
-        let mut sum: u64 = 0;
-        for j in 0..64 {
-            sum += (matrix[i][j] as u64) * (v[j] as u64);
-        }
-        p[i] = (sum >> 10) & 0x0F;
-    }
-    
+    let mut p = [0u16; 64];
+    matrix_mul(matrix, &v, &mut p);
+
     // XOR back
     let mut digest = [0u8; 32];
     for i in 0..32 {
@@ -159,7 +308,7 @@ fn heavy_hash_internal(matrix: &Matrix, hash: &[u8; 32]) -> [u8; 32] {
     }
     
     // Final cSHAKE256
-    let result = cshake256(b"HeavyHash", &digest, 32);
+    let result = cshake256_core(&cores.heavy, &digest, 32);
     result.try_into().unwrap()
 }
 
@@ -173,19 +322,20 @@ fn calculate_pow_internal(
     timestamp: u64,
     nonce: u64,
     matrix: &Matrix,
+    cores: &ShakeCores,
 ) -> [u8; 32] {
     // Build 80-byte header
     let mut header = [0u8; 80];
     header[0..32].copy_from_slice(pre_pow_hash);
     header[32..40].copy_from_slice(&timestamp.to_le_bytes());
     header[72..80].copy_from_slice(&nonce.to_le_bytes());
-    
+
     // First hash
-    let pow_hash = cshake256(b"ProofOfWorkHash", &header, 32);
+    let pow_hash = cshake256_core(&cores.pow, &header, 32);
     let pow_hash: [u8; 32] = pow_hash.try_into().unwrap();
-    
+
     // HeavyHash
-    heavy_hash_internal(matrix, &pow_hash)
+    heavy_hash_internal(matrix, &pow_hash, cores)
 }
 
 #[inline]
@@ -214,17 +364,34 @@ fn compare_u256(a: &[u64; 4], b: &[u64; 4]) -> std::cmp::Ordering {
 // Mining State (kept in Rust to avoid FFI overhead)
 // ═══════════════════════════════════════════════════════════════════════════════
 
+#[derive(Clone)]
 struct MiningState {
     pre_pow_hash: [u8; 32],
     timestamp: u64,
     target: [u64; 4],
     matrix: Matrix,
+    cores: ShakeCores,
+}
+
+// Per-worker nonce generator: each worker walks a disjoint residue class of the
+// counter space (counter ≡ worker_id mod worker_count) and maps it through the
+// single shared `feistel_permute` bijection. Because the counters are disjoint
+// and the map is injective, the resulting nonces are disjoint too — cooperating
+// miners never overlap.
+struct WorkerState {
+    worker_count: u64,
+    counter: u64,
 }
 
 lazy_static::lazy_static! {
     static ref MINING_STATE: Mutex<Option<MiningState>> = Mutex::new(None);
+    static ref MINING_CLOCK: Mutex<Option<Instant>> = Mutex::new(None);
+    static ref WORKER_STATE: Mutex<Option<WorkerState>> = Mutex::new(None);
 }
 
+// Live hash counter read back by `get_hashrate` without an FFI round-trip per nonce.
+static HASHES_DONE: AtomicU64 = AtomicU64::new(0);
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Python Bindings
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -269,7 +436,7 @@ fn compute_pow(py: Python, pre_pow_hash: &[u8], timestamp: u64, nonce: u64, matr
         }
     }
     
-    let result = calculate_pow_internal(&hash, timestamp, nonce, &matrix);
+    let result = calculate_pow_internal(&hash, timestamp, nonce, &matrix, &ShakeCores::new());
     Ok(PyBytes::new(py, &result).into())
 }
 
@@ -293,8 +460,9 @@ fn setup_mining(pre_pow_hash: &[u8], timestamp: u64, target_bytes: &[u8]) -> PyR
         timestamp,
         target,
         matrix,
+        cores: ShakeCores::new(),
     };
-    
+
     *MINING_STATE.lock().unwrap() = Some(state);
     Ok(())
 }
@@ -313,6 +481,7 @@ fn mine_batch(py: Python, nonces: Vec<u64>) -> PyResult<Option<(u64, PyObject)>>
             state.timestamp,
             nonce,
             &state.matrix,
+            &state.cores,
         );
         
         let pow_value = hash_to_u256_le(&pow_hash);
@@ -349,6 +518,7 @@ fn mine_range(py: Python, start_nonce: u64, count: u64, random_mode: bool) -> Py
             state.timestamp,
             nonce,
             &state.matrix,
+            &state.cores,
         );
         
         let pow_value = hash_to_u256_le(&pow_hash);
@@ -361,6 +531,151 @@ fn mine_range(py: Python, start_nonce: u64, count: u64, random_mode: bool) -> Py
     Ok((None, None, count))
 }
 
+/// Mine a contiguous nonce range across `threads` worker threads.
+/// Returns: (found_nonce, pow_hash, hashes_done) or (None, None, hashes_done)
+#[pyfunction]
+fn mine_parallel(py: Python, start_nonce: u64, count: u64, threads: usize) -> PyResult<(Option<u64>, Option<PyObject>, u64)> {
+    // Snapshot the read-only state once so workers share it without holding the lock.
+    let state = {
+        let state_guard = MINING_STATE.lock().unwrap();
+        let state = state_guard.as_ref()
+            .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Call setup_mining first"))?;
+        Arc::new(state.clone())
+    };
+
+    let threads = threads.max(1);
+    let found = Arc::new(AtomicBool::new(false));
+    let result: Arc<Mutex<Option<(u64, [u8; 32])>>> = Arc::new(Mutex::new(None));
+
+    HASHES_DONE.store(0, Ordering::Relaxed);
+    *MINING_CLOCK.lock().unwrap() = Some(Instant::now());
+
+    py.allow_threads(|| {
+        let mut handles = Vec::with_capacity(threads);
+        for t in 0..threads {
+            let state = Arc::clone(&state);
+            let found = Arc::clone(&found);
+            let result = Arc::clone(&result);
+            let step = threads as u64;
+            handles.push(std::thread::spawn(move || {
+                // Interleave the interval so each worker covers a strided slice of it.
+                let mut i = t as u64;
+                let mut local: u64 = 0;
+                while i < count {
+                    if found.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let nonce = start_nonce.wrapping_add(i);
+                    let pow_hash = calculate_pow_internal(
+                        &state.pre_pow_hash,
+                        state.timestamp,
+                        nonce,
+                        &state.matrix,
+                        &state.cores,
+                    );
+
+                    local += 1;
+                    if local & 0x3FF == 0 {
+                        HASHES_DONE.fetch_add(local, Ordering::Relaxed);
+                        local = 0;
+                    }
+
+                    let pow_value = hash_to_u256_le(&pow_hash);
+                    if compare_u256(&pow_value, &state.target) == std::cmp::Ordering::Less {
+                        found.store(true, Ordering::Relaxed);
+                        *result.lock().unwrap() = Some((nonce, pow_hash));
+                        break;
+                    }
+
+                    i += step;
+                }
+                HASHES_DONE.fetch_add(local, Ordering::Relaxed);
+            }));
+        }
+        for handle in handles {
+            let _ = handle.join();
+        }
+    });
+
+    let hashes = HASHES_DONE.load(Ordering::Relaxed);
+    let result = result.lock().unwrap();
+    match result.as_ref() {
+        Some((nonce, pow_hash)) => Ok((Some(*nonce), Some(PyBytes::new(py, pow_hash).into()), hashes)),
+        None => Ok((None, None, hashes)),
+    }
+}
+
+/// Read the live hash counter and the seconds elapsed since the current run began.
+/// Returns: (hashes_done, elapsed_secs)
+#[pyfunction]
+fn get_hashrate() -> PyResult<(u64, f64)> {
+    let hashes = HASHES_DONE.load(Ordering::Relaxed);
+    let elapsed = MINING_CLOCK.lock().unwrap()
+        .map(|start| start.elapsed().as_secs_f64())
+        .unwrap_or(0.0);
+    Ok((hashes, elapsed))
+}
+
+/// Configure this process as worker `worker_id` of `worker_count` cooperating miners.
+/// Each worker deterministically covers a disjoint residue class of the counter space.
+#[pyfunction]
+fn setup_worker(worker_id: u64, worker_count: u64) -> PyResult<()> {
+    if worker_count == 0 || worker_id >= worker_count {
+        return Err(pyo3::exceptions::PyValueError::new_err("need 0 <= worker_id < worker_count"));
+    }
+
+    let state = WorkerState {
+        // Disjoint input residue class; the shared permutation preserves disjointness.
+        worker_count,
+        counter: worker_id,
+    };
+
+    *WORKER_STATE.lock().unwrap() = Some(state);
+
+    // Start the live-hashrate clock here so `get_hashrate` tracks worker-mode
+    // mining just as it does `mine_parallel`.
+    HASHES_DONE.store(0, Ordering::Relaxed);
+    *MINING_CLOCK.lock().unwrap() = Some(Instant::now());
+    Ok(())
+}
+
+/// Mine the next `batch` nonces drawn from this worker's slice of the shared permutation.
+/// The counter is advanced so a later call resumes exactly where this one stopped.
+/// Returns: (found_nonce, pow_hash, hashes_done) or (None, None, hashes_done)
+#[pyfunction]
+fn mine_worker(py: Python, batch: u64) -> PyResult<(Option<u64>, Option<PyObject>, u64)> {
+    let state_guard = MINING_STATE.lock().unwrap();
+    let state = state_guard.as_ref()
+        .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Call setup_mining first"))?;
+
+    let mut worker_guard = WORKER_STATE.lock().unwrap();
+    let worker = worker_guard.as_mut()
+        .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Call setup_worker first"))?;
+
+    for i in 0..batch {
+        let nonce = feistel_permute(worker.counter);
+        worker.counter = worker.counter.wrapping_add(worker.worker_count);
+
+        let pow_hash = calculate_pow_internal(
+            &state.pre_pow_hash,
+            state.timestamp,
+            nonce,
+            &state.matrix,
+            &state.cores,
+        );
+
+        let pow_value = hash_to_u256_le(&pow_hash);
+
+        if compare_u256(&pow_value, &state.target) == std::cmp::Ordering::Less {
+            HASHES_DONE.fetch_add(i + 1, Ordering::Relaxed);
+            return Ok((Some(nonce), Some(PyBytes::new(py, &pow_hash).into()), i + 1));
+        }
+    }
+
+    HASHES_DONE.fetch_add(batch, Ordering::Relaxed);
+    Ok((None, None, batch))
+}
+
 /// Get current mining state hash (for checking if template changed)
 #[pyfunction]
 fn get_state_hash() -> PyResult<Option<Vec<u8>>> {
@@ -378,6 +693,73 @@ fn kaspa_pow_py(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(setup_mining, m)?)?;
     m.add_function(wrap_pyfunction!(mine_batch, m)?)?;
     m.add_function(wrap_pyfunction!(mine_range, m)?)?;
+    m.add_function(wrap_pyfunction!(mine_parallel, m)?)?;
+    m.add_function(wrap_pyfunction!(get_hashrate, m)?)?;
+    m.add_function(wrap_pyfunction!(setup_worker, m)?)?;
+    m.add_function(wrap_pyfunction!(mine_worker, m)?)?;
     m.add_function(wrap_pyfunction!(get_state_hash, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Straightforward u64 reference, matching the original scalar multiply before
+    // the u16-accumulator and AVX2 rewrites.
+    fn matrix_mul_reference(matrix: &Matrix, v: &[u16; 64], p: &mut [u16; 64]) {
+        for i in 0..64 {
+            let mut sum: u64 = 0;
+            for j in 0..64 {
+                sum += matrix[i][j] as u64 * v[j] as u64;
+            }
+            p[i] = ((sum >> 10) & 0x0F) as u16;
+        }
+    }
+
+    // Fill a matrix and vector with the 4-bit entries the real hash path produces.
+    fn random_inputs(rng: &mut Xoshiro256PlusPlus) -> (Matrix, [u16; 64]) {
+        let mut matrix = [[0u16; 64]; 64];
+        for row in matrix.iter_mut() {
+            for j in (0..64).step_by(16) {
+                let val = rng.next();
+                for k in 0..16 {
+                    row[j + k] = ((val >> (4 * k)) & 0x0F) as u16;
+                }
+            }
+        }
+        let mut v = [0u16; 64];
+        let mut j = 0;
+        while j < 64 {
+            let val = rng.next();
+            for k in 0..16 {
+                v[j + k] = ((val >> (4 * k)) & 0x0F) as u16;
+            }
+            j += 16;
+        }
+        (matrix, v)
+    }
+
+    #[test]
+    fn matrix_mul_matches_reference() {
+        let mut rng = Xoshiro256PlusPlus::new(0x0123_4567, 0x89ab_cdef, 0xdead_beef, 0xfeed_face);
+        for _ in 0..256 {
+            let (matrix, v) = random_inputs(&mut rng);
+
+            let mut expected = [0u16; 64];
+            matrix_mul_reference(&matrix, &v, &mut expected);
+
+            let mut scalar = [0u16; 64];
+            matrix_mul_scalar(&matrix, &v, &mut scalar);
+            assert_eq!(scalar, expected, "scalar kernel diverged from reference");
+
+            #[cfg(target_arch = "x86_64")]
+            if is_x86_feature_detected!("avx2") {
+                let mut avx2 = [0u16; 64];
+                // SAFETY: guarded by the runtime AVX2 feature check above.
+                unsafe { matrix_mul_avx2(&matrix, &v, &mut avx2) };
+                assert_eq!(avx2, expected, "avx2 kernel diverged from reference");
+            }
+        }
+    }
+}